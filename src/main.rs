@@ -1,41 +1,237 @@
 use std::env;
 use std::error::Error;
+use std::fs;
 use reqwest;
 use serde::{Deserialize, Serialize};
 use serde_json;
 use colored::*;
 use chrono::{DateTime, Utc};
+use clap::Parser;
+use log;
+
+/// A small command-line weather reporter backed by the OpenWeatherMap API.
+#[derive(Parser, Debug)]
+#[command(name = "weather-cli", about = "Fetch and display the current weather for a city")]
+struct Cli {
+    /// Location to look up: a city name, `lat,lon` coordinates, or a numeric city ID
+    /// (overrides the config file and the default; ignored when the config lists `users`)
+    #[arg(short, long)]
+    city: Option<String>,
+
+    /// Units to request from the API (metric, imperial, standard)
+    #[arg(short, long)]
+    units: Option<String>,
+
+    /// Language for the weather description (e.g. en, uk, de)
+    #[arg(long)]
+    lang: Option<String>,
+
+    /// Path to a JSON config file with api_key, default_city and units
+    #[arg(long)]
+    config: Option<String>,
+
+    /// Poll every N seconds (at least 600) instead of exiting after one report, printing only on change
+    #[arg(long)]
+    watch: Option<u64>,
+
+    /// Push each user's weather summary to their Slack status (requires config `users`,
+    /// each with their own `slack_token`, or a SLACK_API_TOKEN fallback for a single user)
+    #[arg(long)]
+    slack: bool,
+}
+
+/// OpenWeatherMap refreshes data roughly every 10 minutes, so polling faster than this
+/// just burns requests without getting fresher data.
+const MIN_WATCH_INTERVAL_SECS: u64 = 600;
+
+/// Temperature and measurement units to request from the API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Units {
+    Metric,
+    Imperial,
+    Standard,
+}
+
+impl Units {
+    /// The value of the API's `units` query parameter for this variant.
+    fn api_param(&self) -> &'static str {
+        match self {
+            Units::Metric => "metric",
+            Units::Imperial => "imperial",
+            Units::Standard => "standard",
+        }
+    }
+
+    fn temp_symbol(&self) -> &'static str {
+        match self {
+            Units::Metric => "°C",
+            Units::Imperial => "°F",
+            Units::Standard => "K",
+        }
+    }
+
+    fn wind_unit(&self) -> &'static str {
+        match self {
+            Units::Imperial => "mph",
+            Units::Metric | Units::Standard => "m/s",
+        }
+    }
+}
+
+impl Default for Units {
+    fn default() -> Self {
+        Units::Standard
+    }
+}
+
+impl std::str::FromStr for Units {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "metric" => Ok(Units::Metric),
+            "imperial" => Ok(Units::Imperial),
+            "standard" => Ok(Units::Standard),
+            other => Err(format!("Unknown units '{}': expected metric, imperial, or standard", other)),
+        }
+    }
+}
+
+/// A location to query the API for, as either coordinates, a numeric city ID, or a
+/// free-text place name.
+///
+/// Disambiguating these up front avoids the API guessing among cities that share a name
+/// across countries.
+#[derive(Debug, Clone, PartialEq)]
+enum Location {
+    Coords(f64, f64),
+    CityId(u64),
+    Place(String),
+}
+
+impl Location {
+    /// The `lat`/`lon`, `id`, or `q` query parameter fragment for this location.
+    fn query_param(&self) -> String {
+        match self {
+            Location::Coords(lat, lon) => format!("lat={}&lon={}", lat, lon),
+            Location::CityId(id) => format!("id={}", id),
+            Location::Place(name) => format!("q={}", name),
+        }
+    }
+}
+
+/// Parse a location given as `lat,lon` coordinates, a numeric city ID, or a place name.
+///
+/// This never fails: anything that isn't coordinates or an all-digit ID is treated as a
+/// free-text place name, same as the API's own `q=` parameter.
+fn parse_location(input: &str) -> Location {
+    let parts: Vec<&str> = input.split(',').collect();
+    if parts.len() == 2 {
+        if let (Ok(lat), Ok(lon)) = (parts[0].trim().parse::<f64>(), parts[1].trim().parse::<f64>()) {
+            return Location::Coords(lat, lon);
+        }
+    }
+
+    if !input.is_empty() && input.chars().all(|c| c.is_ascii_digit()) {
+        if let Ok(id) = input.parse::<u64>() {
+            return Location::CityId(id);
+        }
+    }
+
+    Location::Place(input.to_string())
+}
+
+/// Format a raw temperature value already expressed in `units` for display.
+///
+/// The API returns the temperature pre-converted for `metric`/`imperial`, so this
+/// does no arithmetic of its own -- it just attaches the right symbol.
+fn format_temperature(value: f64, units: Units) -> String {
+    format!("{:.1}{}", value, units.temp_symbol())
+}
+
+/// Settings loaded from an optional JSON config file.
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct Config {
+    #[serde(default)]
+    api_key: Option<String>,
+
+    #[serde(default)]
+    default_city: Option<String>,
+
+    #[serde(default)]
+    units: Option<String>,
+
+    #[serde(default)]
+    lang: Option<String>,
+
+    /// Multiple users to report weather for, e.g. for a team status tool. When this is
+    /// non-empty, `--city` and `default_city` are ignored in favor of per-user locations.
+    #[serde(default)]
+    users: Vec<ConfigUser>,
+}
+
+impl Config {
+    fn load(path: &str) -> Result<Config, Box<dyn Error>> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read config file '{}': {}", path, e))?;
+        serde_json::from_str(&contents)
+            .map_err(|e| format!("Failed to deserialize configuration JSON: {}", e).into())
+    }
+}
+
+/// A single team member entry in a multi-location config.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct ConfigUser {
+    name: String,
+    location: String,
+
+    /// A user's own Slack token, needed because `users.profile.set` can only mutate the
+    /// profile that owns the token used to call it -- one shared token cannot set
+    /// per-user statuses.
+    #[serde(default)]
+    slack_token: Option<String>,
+}
 
 // Added derive Debug for better diagnostics
 #[derive(Serialize, Deserialize, Debug)]
 struct WeatherResponse {
     #[serde(default)]
     main: MainIndicators,
-    
+
     #[serde(default)]
     weather: Vec<WeatherDescription>,
-    
+
     #[serde(default)]
     wind: Wind,
-    
+
     #[serde(default)]
     name: String,
-    
+
     #[serde(default)]
     sys: SystemInfo,
 }
 
+impl WeatherResponse {
+    /// Current temperature. `get_weather` only ever returns `Ok` once this field has been
+    /// confirmed present, so display code can read it as a plain `f64`.
+    fn temp(&self) -> f64 {
+        self.main.temp.unwrap_or_default()
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Default)]
 struct MainIndicators {
-    #[serde(default)]
-    temp: f64,
-    
+    // An Option rather than a defaulted f64: a missing field deserializes to None, which
+    // `get_weather` rejects as an error, whereas 0.0 is a valid temperature reading
+    // (0 °C / 0 °F), so it can't double as an absent-value sentinel.
+    temp: Option<f64>,
+
     #[serde(default)]
     feels_like: f64,
-    
+
     #[serde(default)]
     humidity: u8,
-    
+
     #[serde(default)]
     pressure: u16,
 }
@@ -56,18 +252,24 @@ struct Wind {
 struct SystemInfo {
     #[serde(default)]
     country: String,
-    
+
     #[serde(default)]
     sunrise: u64,
-    
+
     #[serde(default)]
     sunset: u64,
 }
 
-fn convert_temperature(kelvin: f64) -> (f64, f64) {
-    let celsius = kelvin - 273.15;
-    let fahrenheit = (celsius * 9.0/5.0) + 32.0;
-    (celsius, fahrenheit)
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct ForecastResponse {
+    #[serde(default)]
+    list: Vec<ForecastEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct ForecastEntry {
+    #[serde(default)]
+    main: MainIndicators,
 }
 
 fn format_time(timestamp: u64) -> String {
@@ -76,6 +278,41 @@ fn format_time(timestamp: u64) -> String {
     datetime.format("%H:%M").to_string()
 }
 
+/// A coarse summary of a `WeatherResponse` used to detect meaningful changes between polls.
+///
+/// Rounding the temperature and wind speed avoids reprinting on sub-degree jitter between
+/// polls that don't reflect an actual change in conditions.
+#[derive(Debug, Clone, PartialEq)]
+struct ReportSummary {
+    description: String,
+    temp_bucket: i64,
+    wind_bucket: i64,
+}
+
+impl ReportSummary {
+    fn from_weather(weather: &WeatherResponse) -> ReportSummary {
+        ReportSummary {
+            description: weather
+                .weather
+                .first()
+                .map(|w| w.description.clone())
+                .unwrap_or_else(|| "Unknown".to_string()),
+            temp_bucket: weather.temp().round() as i64,
+            wind_bucket: weather.wind.speed.round() as i64,
+        }
+    }
+}
+
+fn get_trend(current: f64, next: f64) -> &'static str {
+    if next - current > 1.0 {
+        "↑"
+    } else if current - next > 1.0 {
+        "↓"
+    } else {
+        "→"
+    }
+}
+
 fn get_weather_emoji(description: &str) -> &str {
     match description.to_lowercase().as_str() {
         x if x.contains("clear") => "☀️",
@@ -88,87 +325,310 @@ fn get_weather_emoji(description: &str) -> &str {
     }
 }
 
+/// Fetch and parse the current weather for a location. This is the single reusable entry
+/// point for both the one-shot/watch flow and the per-user team report, which differ only
+/// in how they react to an `Err`.
+async fn get_weather(
+    client: &reqwest::Client,
+    location: &Location,
+    api_key: &str,
+    units: Units,
+    lang: &str,
+) -> Result<WeatherResponse, Box<dyn Error>> {
+    let url = format!(
+        "https://api.openweathermap.org/data/2.5/weather?{}&appid={}&units={}&lang={}",
+        location.query_param(), api_key, units.api_param(), lang
+    );
+
+    let response_text = client.get(&url).send().await?.text().await?;
+
+    let weather: WeatherResponse = serde_json::from_str(&response_text)
+        .map_err(|e| format!("JSON parsing error: {} (response: {})", e, response_text))?;
+
+    if weather.main.temp.is_none() {
+        return Err(format!("API response is missing a temperature reading: {}", response_text).into());
+    }
+
+    Ok(weather)
+}
+
+/// Fetch the forecast's next entry temperature. The forecast is a nice-to-have: on any
+/// failure this logs under `RUST_LOG` and returns `None` rather than aborting the report.
+async fn fetch_forecast_temp(client: &reqwest::Client, forecast_url: &str) -> Option<f64> {
+    match client.get(forecast_url).send().await {
+        Ok(resp) => match resp.json::<ForecastResponse>().await {
+            Ok(forecast) => {
+                let next_temp = forecast.list.first().and_then(|entry| entry.main.temp);
+                if next_temp.is_none() {
+                    log::warn!("Forecast response has no upcoming temperature");
+                }
+                next_temp
+            }
+            Err(e) => {
+                log::warn!("Failed to parse forecast response: {}", e);
+                None
+            }
+        },
+        Err(e) => {
+            log::warn!("Failed to fetch forecast: {}", e);
+            None
+        }
+    }
+}
+
+/// Render a full weather report as a single string.
+///
+/// Building the whole report before printing lets callers emit it with one `println!`, so
+/// concurrent reports (e.g. from `run_team_report`) can't interleave line-by-line.
+fn render_report(weather: &WeatherResponse, next_forecast_temp: Option<f64>, units: Units) -> String {
+    let default_description = WeatherDescription { description: "Unknown".to_string() };
+    let weather_description = weather.weather.first().unwrap_or(&default_description);
+    let emoji = get_weather_emoji(&weather_description.description);
+
+    let mut out = String::new();
+    out.push_str(&format!("{} Weather Report {}\n", "🌍".green(), "🌍".green()));
+    out.push_str(&format!("{} {}, {}\n", emoji, weather.name.blue(), weather.sys.country.blue()));
+
+    out.push_str(&format!("\n{} Weather Conditions:\n", "📊".yellow()));
+    out.push_str(&format!("   {}: {}\n", "Status".green(), weather_description.description.yellow()));
+    match next_forecast_temp {
+        Some(next_temp) => out.push_str(&format!(
+            "   {}: {} → {} {}\n",
+            "Temperature".green(),
+            format_temperature(weather.temp(), units),
+            format_temperature(next_temp, units),
+            get_trend(weather.temp(), next_temp)
+        )),
+        None => out.push_str(&format!("   {}: {}\n", "Temperature".green(), format_temperature(weather.temp(), units))),
+    }
+    out.push_str(&format!("   {}: {}\n", "Feels like".green(), format_temperature(weather.main.feels_like, units)));
+
+    out.push_str(&format!("\n{} Additional Details:\n", "🌬️".cyan()));
+    out.push_str(&format!("   {}: {}%\n", "Humidity".green(), weather.main.humidity));
+    out.push_str(&format!("   {}: {:.1} {}\n", "Wind speed".green(), weather.wind.speed, units.wind_unit()));
+    out.push_str(&format!("   {}: {} hPa\n", "Pressure".green(), weather.main.pressure));
+
+    out.push_str(&format!("\n{} Celestial Events:\n", "🌅".magenta()));
+    out.push_str(&format!("   {}: {}\n", "Sunrise".green(), format_time(weather.sys.sunrise)));
+    out.push_str(&format!("   {}: {}", "Sunset".green(), format_time(weather.sys.sunset)));
+
+    out
+}
+
+fn print_report(weather: &WeatherResponse, next_forecast_temp: Option<f64>, units: Units) {
+    println!("{}", render_report(weather, next_forecast_temp, units));
+}
+
+/// Build a short emoji + temperature summary suitable for a Slack status line.
+fn build_slack_summary(weather: &WeatherResponse, units: Units) -> String {
+    let description = weather
+        .weather
+        .first()
+        .map(|w| w.description.as_str())
+        .unwrap_or("Unknown");
+    let emoji = get_weather_emoji(description);
+    format!("{} {}", emoji, format_temperature(weather.temp(), units))
+}
+
+/// Slack wraps API errors in a 200 response, so the real result lives in `ok`/`error`.
+#[derive(Deserialize, Debug)]
+struct SlackApiResponse {
+    ok: bool,
+
+    #[serde(default)]
+    error: Option<String>,
+}
+
+/// Push a status text to Slack via `users.profile.set`, authenticating with `token`.
+async fn post_slack_status(client: &reqwest::Client, token: &str, status_text: &str) -> Result<(), Box<dyn Error>> {
+    let response = client
+        .post("https://slack.com/api/users.profile.set")
+        .bearer_auth(token)
+        .json(&serde_json::json!({
+            "profile": { "status_text": status_text }
+        }))
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        return Err(format!("Slack API returned status {}", response.status()).into());
+    }
+
+    let body: SlackApiResponse = response.json().await?;
+    if !body.ok {
+        let error = body.error.unwrap_or_else(|| "unknown error".to_string());
+        return Err(format!("Slack API rejected the status update: {}", error).into());
+    }
+
+    Ok(())
+}
+
+/// Fetch weather for every configured user concurrently, printing a report for each and
+/// optionally pushing a Slack status summary.
+///
+/// `users.profile.set` can only mutate the profile owning the token it's called with, so
+/// each user's own `slack_token` (if configured) is used for their push; `push_slack`
+/// without a per-user token falls back to `default_slack_token`, e.g. for a single-user
+/// team where the env var token already belongs to that person.
+async fn run_team_report(
+    client: &reqwest::Client,
+    users: &[ConfigUser],
+    api_key: &str,
+    units: Units,
+    lang: &str,
+    push_slack: bool,
+    default_slack_token: Option<&str>,
+) {
+    let mut handles = Vec::new();
+
+    for user in users {
+        let client = client.clone();
+        let api_key = api_key.to_string();
+        let lang = lang.to_string();
+        let name = user.name.clone();
+        let location = parse_location(&user.location);
+        let slack_token = user.slack_token.clone().or_else(|| default_slack_token.map(|t| t.to_string()));
+
+        // Each task builds its report into a buffer and prints it in one shot, so
+        // concurrent fetches don't interleave their output line-by-line.
+        handles.push(tokio::spawn(async move {
+            match get_weather(&client, &location, &api_key, units, &lang).await {
+                Ok(weather) => {
+                    let mut report = format!("\n{} {}\n", "👤".cyan(), name.blue());
+                    report.push_str(&render_report(&weather, None, units));
+                    println!("{}", report);
+
+                    if push_slack {
+                        match slack_token {
+                            Some(token) => {
+                                let summary = build_slack_summary(&weather, units);
+                                if let Err(e) = post_slack_status(&client, &token, &summary).await {
+                                    log::warn!("Failed to update Slack status for {}: {}", name, e);
+                                }
+                            }
+                            None => log::warn!("No Slack token configured for {}; skipping status update", name),
+                        }
+                    }
+                }
+                Err(e) => eprintln!("Error fetching weather for {}: {}", name, e),
+            }
+        }));
+    }
+
+    for handle in handles {
+        let _ = handle.await;
+    }
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
     // Adding RUST_LOG for detailed diagnostics
     env_logger::init();
 
-    let api_key = match env::var("OPENWEATHERMAP_API_KEY") {
-        Ok(key) => key,
-        Err(_) => {
-            eprintln!("Error: OpenWeatherMap API key not found.");
-            eprintln!("Please set the OPENWEATHERMAP_API_KEY environment variable.");
-            std::process::exit(1);
-        }
+    let cli = Cli::parse();
+
+    let config = match &cli.config {
+        Some(path) => match Config::load(path) {
+            Ok(config) => config,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        },
+        None => Config::default(),
     };
 
-    let args: Vec<String> = env::args().collect();
-    let city = if args.len() > 1 { &args[1] } else { "Kyiv" };
+    // The config file overrides the environment variable; there is no flag for the API key.
+    let api_key = config.api_key.clone().or_else(|| env::var("OPENWEATHERMAP_API_KEY").ok());
 
-    let url = format!(
-        "https://api.openweathermap.org/data/2.5/weather?q={}&appid={}&lang=en",
-        city, api_key
-    );
-
-    let client = reqwest::Client::new();
-    let response = match client.get(&url).send().await {
-        Ok(resp) => resp,
-        Err(e) => {
-            eprintln!("Network error: {}", e);
+    let api_key = match api_key {
+        Some(key) => key,
+        None => {
+            eprintln!("Error: OpenWeatherMap API key not found.");
+            eprintln!("Please pass --config with an api_key field or set the OPENWEATHERMAP_API_KEY environment variable.");
             std::process::exit(1);
         }
     };
 
-    // Retrieve response text for diagnostics
-    let response_text = match response.text().await {
-        Ok(text) => text,
-        Err(e) => {
-            eprintln!("Error fetching response text: {}", e);
-            std::process::exit(1);
-        }
+    let units_arg = cli.units.clone().or(config.units.clone());
+    let units: Units = match units_arg {
+        Some(u) => match u.parse() {
+            Ok(units) => units,
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                std::process::exit(1);
+            }
+        },
+        None => Units::default(),
     };
 
-    // Add detailed JSON diagnostics
-    println!("Received JSON response: {}", response_text);
+    let lang = cli.lang.clone().or(config.lang.clone()).unwrap_or_else(|| "en".to_string());
 
-    let weather: WeatherResponse = match serde_json::from_str(&response_text) {
-        Ok(data) => data,
-        Err(e) => {
-            eprintln!("JSON parsing error: {}", e);
-            eprintln!("Response details: {}", response_text);
-            std::process::exit(1);
-        }
-    };
+    let client = reqwest::Client::new();
+
+    if !config.users.is_empty() {
+        // SLACK_API_TOKEN is only a fallback for users without their own `slack_token` in
+        // the config; users.profile.set can only mutate the profile owning the token.
+        let default_slack_token = env::var("SLACK_API_TOKEN").ok();
 
-    // Additional data verification
-    if weather.main.temp == 0.0 {
-        eprintln!("Warning: Unable to retrieve temperature. Check the city and API key.");
-        std::process::exit(1);
+        run_team_report(&client, &config.users, &api_key, units, &lang, cli.slack, default_slack_token.as_deref()).await;
+        return Ok(());
     }
 
-    let (temp_celsius, temp_fahrenheit) = convert_temperature(weather.main.temp);
-    let (feels_temp_celsius, feels_temp_fahrenheit) = convert_temperature(weather.main.feels_like);
+    let city = cli
+        .city
+        .clone()
+        .or(config.default_city.clone())
+        .unwrap_or_else(|| "Kyiv".to_string());
+    let location = parse_location(&city);
 
-    let default_description = WeatherDescription { description: "Unknown".to_string() };
-    let weather_description = weather.weather.first().unwrap_or(&default_description);
+    let forecast_url = format!(
+        "https://api.openweathermap.org/data/2.5/forecast?{}&appid={}&units={}&lang={}",
+        location.query_param(), api_key, units.api_param(), lang
+    );
 
-    let emoji = get_weather_emoji(&weather_description.description);
-    println!("{} Weather Report {}", "🌍".green(), "🌍".green());
-    println!("{} {}, {}", emoji, weather.name.blue(), weather.sys.country.blue());
-    
-    println!("\n{} Weather Conditions:", "📊".yellow());
-    println!("   {}: {}", "Status".green(), weather_description.description.yellow());
-    println!("   {}: {:.1}°C / {:.1}°F", "Temperature".green(), temp_celsius, temp_fahrenheit);
-    println!("   {}: {:.1}°C / {:.1}°F", "Feels like".green(), feels_temp_celsius, feels_temp_fahrenheit);
-    
-    println!("\n{} Additional Details:", "🌬️".cyan());
-    println!("   {}: {}%", "Humidity".green(), weather.main.humidity);
-    println!("   {}: {:.1} m/s", "Wind speed".green(), weather.wind.speed);
-    println!("   {}: {} hPa", "Pressure".green(), weather.main.pressure);
-    
-    println!("\n{} Celestial Events:", "🌅".magenta());
-    println!("   {}: {}", "Sunrise".green(), format_time(weather.sys.sunrise));
-    println!("   {}: {}", "Sunset".green(), format_time(weather.sys.sunset));
+    match cli.watch {
+        Some(requested_interval) => {
+            let interval_secs = requested_interval.max(MIN_WATCH_INTERVAL_SECS);
+            if requested_interval < MIN_WATCH_INTERVAL_SECS {
+                log::warn!(
+                    "Requested --watch {}s is below OpenWeatherMap's refresh cadence; polling every {}s instead",
+                    requested_interval,
+                    MIN_WATCH_INTERVAL_SECS
+                );
+            }
 
-    Ok(())
+            let mut last_report: Option<ReportSummary> = None;
+            loop {
+                match get_weather(&client, &location, &api_key, units, &lang).await {
+                    Ok(weather) => {
+                        let next_forecast_temp = fetch_forecast_temp(&client, &forecast_url).await;
+
+                        let summary = ReportSummary::from_weather(&weather);
+                        if last_report.as_ref() != Some(&summary) {
+                            print_report(&weather, next_forecast_temp, units);
+                            last_report = Some(summary);
+                        } else {
+                            log::info!("Conditions in {} unchanged, skipping poll output", city);
+                        }
+                    }
+                    Err(e) => log::warn!("Skipped poll due to error: {}", e),
+                }
+
+                tokio::time::sleep(std::time::Duration::from_secs(interval_secs)).await;
+            }
+        }
+        None => {
+            let weather = match get_weather(&client, &location, &api_key, units, &lang).await {
+                Ok(weather) => weather,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    std::process::exit(1);
+                }
+            };
+            let next_forecast_temp = fetch_forecast_temp(&client, &forecast_url).await;
+            print_report(&weather, next_forecast_temp, units);
+            Ok(())
+        }
+    }
 }